@@ -8,97 +8,130 @@
     unused_import_braces, unused_qualifications
 )]
 
-extern crate ring;
+extern crate digest;
 extern crate rand;
+extern crate signature;
+extern crate subtle;
+extern crate zeroize;
 
+use std::cell::Cell;
+use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+use digest::Digest;
 use rand::OsRng;
 use rand::Rng;
-use ring::digest::{Algorithm, Context};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
+
+mod signature_impl;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod merkle;
+mod winternitz;
+
+pub use signature_impl::LamportSignature;
+pub use merkle::{MerkleKeyPair, MerklePublicKey, MerkleSignature};
+pub use winternitz::{WinternitzPrivateKey, WinternitzPublicKey, WinternitzSignatureData};
 
 /// A type alias defining a Lamport signature
 pub type LamportSignatureData = Vec<Vec<u8>>;
 
-/// A one-time signing public key
-#[derive(Clone, Debug)]
-pub struct PublicKey {
+/// A one-time signing public key, parameterized over the digest `D` used to build it
+#[derive(Clone)]
+pub struct PublicKey<D: Digest + Clone> {
     zero_values: Vec<Vec<u8>>,
     one_values: Vec<Vec<u8>>,
-    algorithm: &'static Algorithm,
+    digest: PhantomData<D>,
 }
 
-impl PartialEq for PublicKey {
-    #[allow(trivial_casts)]
+impl<D: Digest + Clone> fmt::Debug for PublicKey<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PublicKey")
+            .field("zero_values", &self.zero_values)
+            .field("one_values", &self.one_values)
+            .finish()
+    }
+}
+
+impl<D: Digest + Clone> PartialEq for PublicKey<D> {
+    // Constant-time so that comparing two public keys doesn't leak which value bit differs first
     fn eq(&self, other: &Self) -> bool {
-       self.zero_values == other.zero_values &&
-       self.one_values == other.one_values &&
-       self.algorithm as *const Algorithm as usize == other.algorithm as *const Algorithm as usize
-   }
+        if self.zero_values.len() != other.zero_values.len() ||
+           self.one_values.len() != other.one_values.len() {
+            return false;
+        }
+
+        let mut result = Choice::from(1u8);
+        for i in 0..self.zero_values.len() {
+            result &= self.zero_values[i].as_slice().ct_eq(other.zero_values[i].as_slice());
+            result &= self.one_values[i].as_slice().ct_eq(other.one_values[i].as_slice());
+        }
+        result.into()
+    }
 }
 
-impl Hash for PublicKey {
-    #[allow(trivial_casts)]
+impl<D: Digest + Clone> Eq for PublicKey<D> {}
+
+impl<D: Digest + Clone> Hash for PublicKey<D> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.zero_values.hash(state);
         self.one_values.hash(state);
-        (self.algorithm as *const Algorithm as usize).hash(state);
     }
 }
 
-impl Eq for PublicKey {}
-
-/// A one-time signing private key
-#[derive(Clone, Debug)]
-pub struct PrivateKey {
+/// A one-time signing private key, parameterized over the digest `D` used to build it
+#[derive(Clone)]
+pub struct PrivateKey<D: Digest + Clone> {
     // For a n bits hash function: (n * n/8 bytes) for zero_values and one_values
     zero_values: Vec<Vec<u8>>,
     one_values: Vec<Vec<u8>>,
-    algorithm: &'static Algorithm,
-    used: bool,
+    used: Cell<bool>,
+    digest: PhantomData<D>,
+}
+
+impl<D: Digest + Clone> fmt::Debug for PrivateKey<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PrivateKey")
+            .field("zero_values", &self.zero_values)
+            .field("one_values", &self.one_values)
+            .field("used", &self.used.get())
+            .finish()
+    }
 }
 
-impl From<PublicKey> for Vec<u8> {
-    fn from(original: PublicKey) -> Vec<u8> {
+impl<D: Digest + Clone> From<PublicKey<D>> for Vec<u8> {
+    fn from(original: PublicKey<D>) -> Vec<u8> {
         original.to_bytes()
     }
 }
 
-impl PublicKey {
+impl<D: Digest + Clone> PublicKey<D> {
     /// Intializes a public key with a byte vector.
     /// Returns `None` if it couldn't parse the provided data
-    pub fn from_vec(vec: Vec<u8>, algorithm: &'static Algorithm) -> Option<PublicKey> {
+    pub fn from_vec(vec: Vec<u8>) -> Option<PublicKey<D>> {
         let size = vec.len();
-        let hash_output_size = algorithm.output_len;
+        let hash_output_size = <D as Digest>::output_size();
+
+        if hash_output_size == 0 || size % 2 != 0 {
+            return None;
+        }
 
         let mut zero_values_merged = vec;
         let one_values_merged = zero_values_merged.split_off(size / 2);
 
-        let mut zero_values = Vec::new();
-        for i in (0..zero_values_merged.len()).filter(|x| x % hash_output_size == 0) {
-            // indexes for heads
-            let mut sub_vec = Vec::new();
-            for j in 0..hash_output_size {
-                sub_vec.push(zero_values_merged[i + j]);
-            }
-
-            zero_values.push(sub_vec);
+        if zero_values_merged.len() % hash_output_size != 0 || one_values_merged.len() % hash_output_size != 0 {
+            return None;
         }
 
-        let mut one_values = Vec::new();
-        for i in (0..one_values_merged.len()).filter(|x| x % hash_output_size == 0) {
-            // indexes for heads
-            let mut sub_vec = Vec::new();
-            for j in 0..hash_output_size {
-                sub_vec.push(one_values_merged[i + j]);
-            }
-
-            one_values.push(sub_vec);
-        }
+        let zero_values: Vec<Vec<u8>> = zero_values_merged.chunks(hash_output_size).map(|chunk| chunk.to_vec()).collect();
+        let one_values: Vec<Vec<u8>> = one_values_merged.chunks(hash_output_size).map(|chunk| chunk.to_vec()).collect();
 
         Some(PublicKey {
             zero_values: zero_values,
             one_values: one_values,
-            algorithm: algorithm,
+            digest: PhantomData,
         })
     }
 
@@ -112,48 +145,58 @@ impl PublicKey {
 
     /// Verifies that the signature of the data is correctly signed with the given key
     pub fn verify_signature(&self, signature: &LamportSignatureData, data: &[u8]) -> bool {
-        let mut context = Context::new(self.algorithm);
-        context.update(data);
-        let result = context.finish();
-        let data_hash = result.as_ref();
+        let mut hasher = D::new();
+        hasher.update(data);
+        let data_hash = hasher.finalize();
+
+        self.verify_prehashed(signature, &data_hash)
+    }
+
+    /// Verifies a signature against an already-computed digest of the signed data.
+    /// Runs in constant time with respect to which hashed value, if any, fails to match
+    pub(crate) fn verify_prehashed(&self, signature: &LamportSignatureData, data_hash: &[u8]) -> bool {
+        let expected_chunks = data_hash.len() * 8;
+        if signature.len() != expected_chunks ||
+           self.zero_values.len() != expected_chunks ||
+           self.one_values.len() != expected_chunks {
+            return false;
+        }
+
+        let mut result = Choice::from(1u8);
 
         for (i, byte) in data_hash.iter().enumerate() {
             for j in 0..8 {
                 let offset = i * 8 + j;
-                if (byte & (1 << j)) > 0 {
-                    let mut context = Context::new(self.algorithm);
-                    context.update(signature[offset].as_slice());
-                    let hashed_value = Vec::from(context.finish().as_ref());
+                let mut hasher = D::new();
+                hasher.update(signature[offset].as_slice());
+                let hashed_value = hasher.finalize().to_vec();
 
-                    if hashed_value != self.one_values[offset] {
-                        return false;
-                    }
+                let expected = if (byte & (1 << j)) > 0 {
+                    &self.one_values[offset]
                 } else {
-                    let mut context = Context::new(self.algorithm);
-                    context.update(signature[offset].as_slice());
-                    let hashed_value = Vec::from(context.finish().as_ref());
+                    &self.zero_values[offset]
+                };
 
-                    if hashed_value != self.zero_values[offset] {
-                        return false;
-                    }
-                }
+                result &= hashed_value.as_slice().ct_eq(expected.as_slice());
             }
         }
 
-        true
+        result.into()
     }
 }
 
-impl PrivateKey {
+impl<D: Digest + Clone> PrivateKey<D> {
     /// Generates a new random one-time signing key. This method can panic if OS RNG fails
-    pub fn new(algorithm: &'static Algorithm) -> PrivateKey {
+    pub fn new() -> PrivateKey<D> {
+        let output_size = <D as Digest>::output_size();
+
         let generate_bit_hash_values = || -> Vec<Vec<u8>> {
             let mut rng = match OsRng::new() {
                 Ok(g) => g,
                 Err(e) => panic!("Failed to obtain OS RNG: {}", e),
             };
-            let buffer_byte = vec![0u8; algorithm.output_len];
-            let mut buffer = vec![buffer_byte; algorithm.output_len * 8];
+            let buffer_byte = vec![0u8; output_size];
+            let mut buffer = vec![buffer_byte; output_size * 8];
 
             for hash in &mut buffer {
                 rng.fill_bytes(hash)
@@ -168,21 +211,23 @@ impl PrivateKey {
         PrivateKey {
             zero_values: zero_values,
             one_values: one_values,
-            algorithm: algorithm,
-            used: false,
+            used: Cell::new(false),
+            digest: PhantomData,
         }
     }
 
     /// Returns the public key associated with this private key
-    pub fn public_key(&self) -> PublicKey {
+    pub fn public_key(&self) -> PublicKey<D> {
+        let output_size = <D as Digest>::output_size();
+
         let hash_values = |x: &Vec<Vec<u8>>| -> Vec<Vec<u8>> {
-            let buffer_byte = vec![0u8; self.algorithm.output_len];
-            let mut buffer  = vec![buffer_byte; self.algorithm.output_len * 8];
+            let buffer_byte = vec![0u8; output_size];
+            let mut buffer  = vec![buffer_byte; output_size * 8];
 
-            for i in 0 .. self.algorithm.output_len * 8 {
-                let mut context = Context::new(self.algorithm);
-                context.update(x[i].as_slice());
-                buffer[i] = Vec::from(context.finish().as_ref());
+            for i in 0 .. output_size * 8 {
+                let mut hasher = D::new();
+                hasher.update(x[i].as_slice());
+                buffer[i] = hasher.finalize().to_vec();
             }
 
             buffer
@@ -194,22 +239,29 @@ impl PrivateKey {
         PublicKey {
             zero_values: hashed_zero_values,
             one_values: hashed_one_values,
-            algorithm: self.algorithm,
+            digest: PhantomData,
         }
     }
 
     /// Signs the data with the private key and returns the result if successful.
     /// If unsuccesful, an explanation string is returned
     pub fn sign(&mut self, data: &[u8]) -> Result<LamportSignatureData, &'static str> {
-        if self.used {
+        if self.used.get() {
             return Err("Attempting to sign more than once.");
         }
 
-        let mut context = Context::new(self.algorithm);
-        context.update(data);
-        let result    = context.finish();
-        let data_hash = result.as_ref();
+        let mut hasher = D::new();
+        hasher.update(data);
+        let data_hash = hasher.finalize();
+
+        let signature = self.sign_prehashed(&data_hash);
+        self.used.set(true);
+        Ok(signature)
+    }
 
+    /// Builds a signature from an already-computed digest of the data to sign,
+    /// without checking or updating the one-time-use guard
+    pub(crate) fn sign_prehashed(&self, data_hash: &[u8]) -> LamportSignatureData {
         let signature_len = data_hash.len() * 8;
         let mut signature = Vec::with_capacity(signature_len);
 
@@ -225,29 +277,36 @@ impl PrivateKey {
                 }
             }
         }
-        self.used = true;
-        Ok(signature)
+        signature
     }
 }
 
-impl Drop for PrivateKey {
-    fn drop(&mut self) {
-        let zeroize_vector = |vector: &mut Vec<Vec<u8>>| {
-            for v2 in vector.iter_mut() {
-                for byte in v2.iter_mut() {
-                    *byte = 0;
-                }
-            }
-        };
+impl<D: Digest + Clone> Default for PrivateKey<D> {
+    fn default() -> Self {
+        PrivateKey::new()
+    }
+}
 
-        zeroize_vector(&mut self.zero_values);
-        zeroize_vector(&mut self.one_values);
+impl<D: Digest + Clone> Zeroize for PrivateKey<D> {
+    fn zeroize(&mut self) {
+        self.zero_values.zeroize();
+        self.one_values.zeroize();
     }
 }
 
-impl PartialEq for PrivateKey {
-    // ⚠️ This is not a constant-time implementation
-    fn eq(&self, other: &PrivateKey) -> bool {
+// Implemented by hand rather than derived, since `zeroize`'s derive macros would otherwise
+// require `D: Zeroize` even though `D` only ever appears behind a `PhantomData`
+impl<D: Digest + Clone> zeroize::ZeroizeOnDrop for PrivateKey<D> {}
+
+impl<D: Digest + Clone> Drop for PrivateKey<D> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<D: Digest + Clone> PartialEq for PrivateKey<D> {
+    // Constant-time so that comparing two private keys doesn't leak which value bit differs first
+    fn eq(&self, other: &PrivateKey<D>) -> bool {
         if self.one_values.len() != other.one_values.len() {
             return false;
         }
@@ -255,15 +314,15 @@ impl PartialEq for PrivateKey {
             return false;
         }
 
+        let mut result = Choice::from(1u8);
         for i in 0..self.zero_values.len() {
-            if self.zero_values[i] != other.zero_values[i] ||
-               self.one_values[i] != other.one_values[i] {
-                return false;
-            }
+            result &= self.zero_values[i].as_slice().ct_eq(other.zero_values[i].as_slice());
+            result &= self.one_values[i].as_slice().ct_eq(other.one_values[i].as_slice());
         }
-        true
+        result.into()
     }
 }
 
+/// Unit tests for this crate, covering the Lamport, Merkle, and Winternitz signature schemes
 #[cfg(test)]
 pub mod tests;
@@ -0,0 +1,194 @@
+//! Merkle Signature Scheme (MSS): combines `2^height` one-time Lamport key pairs into a single
+//! long-term public key (the Merkle root over the one-time public keys) that can verify up to
+//! `2^height` messages, the same kind of binary-tree-of-hashes structure used for the
+//! transaction Merkle root in a Bitcoin block header.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use digest::Digest;
+
+use crate::{LamportSignatureData, PrivateKey, PublicKey};
+
+/// A Merkle key pair: `2^height` one-time Lamport key pairs committed to by a single Merkle
+/// root, usable to produce up to `2^height` signatures before it is exhausted
+pub struct MerkleKeyPair<D: Digest + Clone> {
+    leaves: Vec<PrivateKey<D>>,
+    tree: Vec<Vec<Vec<u8>>>,
+    height: u32,
+    next_leaf: usize,
+}
+
+impl<D: Digest + Clone> fmt::Debug for MerkleKeyPair<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MerkleKeyPair")
+            .field("height", &self.height)
+            .field("next_leaf", &self.next_leaf)
+            .finish()
+    }
+}
+
+impl<D: Digest + Clone> MerkleKeyPair<D> {
+    /// Generates a new Merkle key pair able to produce up to `2^height` signatures.
+    /// This method can panic if OS RNG fails, same as `PrivateKey::new`
+    pub fn new(height: u32) -> MerkleKeyPair<D> {
+        let num_leaves = 1usize << height;
+
+        let leaves: Vec<PrivateKey<D>> = (0..num_leaves).map(|_| PrivateKey::new()).collect();
+
+        let leaf_hashes: Vec<Vec<u8>> = leaves
+            .iter()
+            .map(|leaf| hash_node::<D>(&leaf.public_key().to_bytes()))
+            .collect();
+
+        let tree = build_tree::<D>(leaf_hashes);
+
+        MerkleKeyPair {
+            leaves,
+            tree,
+            height,
+            next_leaf: 0,
+        }
+    }
+
+    /// Returns the long-term public key associated with this key pair
+    pub fn public_key(&self) -> MerklePublicKey<D> {
+        MerklePublicKey {
+            root: self.tree.last().expect("a Merkle tree always has a root level")[0].clone(),
+            height: self.height,
+            digest: PhantomData,
+        }
+    }
+
+    /// Signs the data with the next unused one-time key pair in the tree, returning an error
+    /// once all `2^height` leaves have been used
+    pub fn sign(&mut self, data: &[u8]) -> Result<MerkleSignature<D>, &'static str> {
+        if self.next_leaf >= self.leaves.len() {
+            return Err("All one-time key pairs in this Merkle tree have been used.");
+        }
+
+        let leaf_index = self.next_leaf;
+        let one_time_public_key = self.leaves[leaf_index].public_key();
+        let one_time_signature = self.leaves[leaf_index].sign(data)?;
+        let auth_path = authentication_path(&self.tree, leaf_index);
+
+        self.next_leaf += 1;
+
+        Ok(MerkleSignature {
+            leaf_index,
+            one_time_public_key,
+            one_time_signature,
+            auth_path,
+        })
+    }
+}
+
+/// The long-term public key of a `MerkleKeyPair`: the root of the Merkle tree built over its
+/// one-time public keys
+#[derive(Clone)]
+pub struct MerklePublicKey<D: Digest + Clone> {
+    root: Vec<u8>,
+    height: u32,
+    digest: PhantomData<D>,
+}
+
+impl<D: Digest + Clone> fmt::Debug for MerklePublicKey<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MerklePublicKey")
+            .field("root", &self.root)
+            .field("height", &self.height)
+            .finish()
+    }
+}
+
+impl<D: Digest + Clone> PartialEq for MerklePublicKey<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root && self.height == other.height
+    }
+}
+
+impl<D: Digest + Clone> Eq for MerklePublicKey<D> {}
+
+impl<D: Digest + Clone> MerklePublicKey<D> {
+    /// Verifies that `signature` is a valid Merkle signature of `data` under this public key:
+    /// the one-time signature must check out against its one-time public key, and that one-time
+    /// public key's leaf hash must fold up through the authentication path to this root
+    pub fn verify_signature(&self, signature: &MerkleSignature<D>, data: &[u8]) -> bool {
+        if signature.auth_path.len() != self.height as usize {
+            return false;
+        }
+
+        if !signature
+            .one_time_public_key
+            .verify_signature(&signature.one_time_signature, data)
+        {
+            return false;
+        }
+
+        let mut node = hash_node::<D>(&signature.one_time_public_key.to_bytes());
+        let mut index = signature.leaf_index;
+
+        for sibling in &signature.auth_path {
+            node = if index.is_multiple_of(2) {
+                hash_pair::<D>(&node, sibling)
+            } else {
+                hash_pair::<D>(sibling, &node)
+            };
+            index /= 2;
+        }
+
+        node == self.root
+    }
+}
+
+/// A signature produced by a `MerkleKeyPair`: a one-time Lamport signature together with
+/// everything needed to authenticate its one-time public key against the long-term Merkle root
+#[derive(Clone, Debug)]
+pub struct MerkleSignature<D: Digest + Clone> {
+    leaf_index: usize,
+    one_time_public_key: PublicKey<D>,
+    one_time_signature: LamportSignatureData,
+    auth_path: Vec<Vec<u8>>,
+}
+
+fn hash_node<D: Digest>(data: &[u8]) -> Vec<u8> {
+    let mut hasher = D::new();
+    hasher.update(data);
+    hasher.finalize().to_vec()
+}
+
+fn hash_pair<D: Digest>(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut hasher = D::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().to_vec()
+}
+
+fn build_tree<D: Digest>(leaf_hashes: Vec<Vec<u8>>) -> Vec<Vec<Vec<u8>>> {
+    let mut tree = vec![leaf_hashes];
+
+    while tree.last().expect("tree is never empty").len() > 1 {
+        let next_level = tree
+            .last()
+            .expect("tree is never empty")
+            .chunks(2)
+            .map(|pair| hash_pair::<D>(&pair[0], &pair[1]))
+            .collect();
+        tree.push(next_level);
+    }
+
+    tree
+}
+
+fn authentication_path(tree: &[Vec<Vec<u8>>], leaf_index: usize) -> Vec<Vec<u8>> {
+    let mut path = Vec::with_capacity(tree.len() - 1);
+    let mut index = leaf_index;
+
+    for level in &tree[..tree.len() - 1] {
+        let sibling_index = index ^ 1;
+        path.push(level[sibling_index].clone());
+        index /= 2;
+    }
+
+    path
+}
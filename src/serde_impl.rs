@@ -0,0 +1,239 @@
+//! Optional `serde` support for `PublicKey`, `PrivateKey`, and `LamportSignature`, enabled by the
+//! `serde` feature. Every serialized value carries the digest's output size alongside the raw
+//! key/signature material, so deserializing is self-describing and fallible: a mismatched or
+//! malformed payload produces a `serde` error. `PublicKey::from_vec`, by contrast, cannot tell
+//! which digest produced its bytes and can accept bytes from a different digest as a key of the
+//! wrong shape.
+
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+
+use digest::Digest;
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde::Deserialize;
+
+use crate::signature_impl::LamportSignature;
+use crate::{PrivateKey, PublicKey};
+
+fn check_output_size<D: Digest, E: de::Error>(tag: u32) -> Result<(), E> {
+    if tag as usize != <D as Digest>::output_size() {
+        return Err(de::Error::custom(format!(
+            "serialized digest output size {} does not match the {} bytes expected by this key type",
+            tag,
+            <D as Digest>::output_size()
+        )));
+    }
+    Ok(())
+}
+
+/// Validates that `values` has exactly one `output_size`-byte entry per hash bit, so a
+/// deserialized key can't later panic with an out-of-bounds index during sign/verify
+fn check_values_shape<D: Digest, E: de::Error>(field: &str, values: &[Vec<u8>]) -> Result<(), E> {
+    let output_size = <D as Digest>::output_size();
+    let expected_len = output_size * 8;
+
+    if values.len() != expected_len || values.iter().any(|v| v.len() != output_size) {
+        return Err(de::Error::custom(format!(
+            "field `{}` must contain exactly {} entries of {} bytes each",
+            field, expected_len, output_size
+        )));
+    }
+    Ok(())
+}
+
+impl<D: Digest + Clone> Serialize for PublicKey<D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PublicKey", 3)?;
+        state.serialize_field("digest_output_size", &(<D as Digest>::output_size() as u32))?;
+        state.serialize_field("zero_values", &self.zero_values)?;
+        state.serialize_field("one_values", &self.one_values)?;
+        state.end()
+    }
+}
+
+impl<'de, D: Digest + Clone> Deserialize<'de> for PublicKey<D> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        #[derive(Deserialize)]
+        #[serde(field_identifier, rename_all = "snake_case")]
+        enum Field {
+            DigestOutputSize,
+            ZeroValues,
+            OneValues,
+        }
+
+        struct PublicKeyVisitor<D>(PhantomData<D>);
+
+        impl<'de, D: Digest + Clone> Visitor<'de> for PublicKeyVisitor<D> {
+            type Value = PublicKey<D>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a serialized Lamport public key")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut digest_output_size = None;
+                let mut zero_values = None;
+                let mut one_values = None;
+
+                while let Some(key) = map.next_key()? {
+                    match key {
+                        Field::DigestOutputSize => digest_output_size = Some(map.next_value()?),
+                        Field::ZeroValues => zero_values = Some(map.next_value()?),
+                        Field::OneValues => one_values = Some(map.next_value()?),
+                    }
+                }
+
+                let digest_output_size: u32 =
+                    digest_output_size.ok_or_else(|| de::Error::missing_field("digest_output_size"))?;
+                check_output_size::<D, A::Error>(digest_output_size)?;
+
+                let zero_values = zero_values.ok_or_else(|| de::Error::missing_field("zero_values"))?;
+                let one_values = one_values.ok_or_else(|| de::Error::missing_field("one_values"))?;
+                check_values_shape::<D, A::Error>("zero_values", &zero_values)?;
+                check_values_shape::<D, A::Error>("one_values", &one_values)?;
+
+                Ok(PublicKey {
+                    zero_values: zero_values,
+                    one_values: one_values,
+                    digest: PhantomData,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct(
+            "PublicKey",
+            &["digest_output_size", "zero_values", "one_values"],
+            PublicKeyVisitor(PhantomData),
+        )
+    }
+}
+
+impl<D: Digest + Clone> Serialize for PrivateKey<D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PrivateKey", 4)?;
+        state.serialize_field("digest_output_size", &(<D as Digest>::output_size() as u32))?;
+        state.serialize_field("zero_values", &self.zero_values)?;
+        state.serialize_field("one_values", &self.one_values)?;
+        state.serialize_field("used", &self.used.get())?;
+        state.end()
+    }
+}
+
+impl<'de, D: Digest + Clone> Deserialize<'de> for PrivateKey<D> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        #[derive(Deserialize)]
+        #[serde(field_identifier, rename_all = "snake_case")]
+        enum Field {
+            DigestOutputSize,
+            ZeroValues,
+            OneValues,
+            Used,
+        }
+
+        struct PrivateKeyVisitor<D>(PhantomData<D>);
+
+        impl<'de, D: Digest + Clone> Visitor<'de> for PrivateKeyVisitor<D> {
+            type Value = PrivateKey<D>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a serialized Lamport private key")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut digest_output_size = None;
+                let mut zero_values = None;
+                let mut one_values = None;
+                let mut used = None;
+
+                while let Some(key) = map.next_key()? {
+                    match key {
+                        Field::DigestOutputSize => digest_output_size = Some(map.next_value()?),
+                        Field::ZeroValues => zero_values = Some(map.next_value()?),
+                        Field::OneValues => one_values = Some(map.next_value()?),
+                        Field::Used => used = Some(map.next_value()?),
+                    }
+                }
+
+                let digest_output_size: u32 =
+                    digest_output_size.ok_or_else(|| de::Error::missing_field("digest_output_size"))?;
+                check_output_size::<D, A::Error>(digest_output_size)?;
+
+                let zero_values = zero_values.ok_or_else(|| de::Error::missing_field("zero_values"))?;
+                let one_values = one_values.ok_or_else(|| de::Error::missing_field("one_values"))?;
+                check_values_shape::<D, A::Error>("zero_values", &zero_values)?;
+                check_values_shape::<D, A::Error>("one_values", &one_values)?;
+
+                Ok(PrivateKey {
+                    zero_values: zero_values,
+                    one_values: one_values,
+                    used: Cell::new(used.ok_or_else(|| de::Error::missing_field("used"))?),
+                    digest: PhantomData,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct(
+            "PrivateKey",
+            &["digest_output_size", "zero_values", "one_values", "used"],
+            PrivateKeyVisitor(PhantomData),
+        )
+    }
+}
+
+impl<D: Digest + Clone> Serialize for LamportSignature<D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("LamportSignature", 2)?;
+        state.serialize_field("digest_output_size", &(<D as Digest>::output_size() as u32))?;
+        state.serialize_field("values", self.as_data())?;
+        state.end()
+    }
+}
+
+impl<'de, D: Digest + Clone> Deserialize<'de> for LamportSignature<D> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        #[derive(Deserialize)]
+        #[serde(field_identifier, rename_all = "snake_case")]
+        enum Field {
+            DigestOutputSize,
+            Values,
+        }
+
+        struct LamportSignatureVisitor<D>(PhantomData<D>);
+
+        impl<'de, D: Digest + Clone> Visitor<'de> for LamportSignatureVisitor<D> {
+            type Value = LamportSignature<D>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a serialized Lamport signature")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut digest_output_size = None;
+                let mut values = None;
+
+                while let Some(key) = map.next_key()? {
+                    match key {
+                        Field::DigestOutputSize => digest_output_size = Some(map.next_value()?),
+                        Field::Values => values = Some(map.next_value()?),
+                    }
+                }
+
+                let digest_output_size: u32 =
+                    digest_output_size.ok_or_else(|| de::Error::missing_field("digest_output_size"))?;
+                check_output_size::<D, A::Error>(digest_output_size)?;
+
+                let values = values.ok_or_else(|| de::Error::missing_field("values"))?;
+                check_values_shape::<D, A::Error>("values", &values)?;
+                Ok(LamportSignature::from_data(values))
+            }
+        }
+
+        deserializer.deserialize_struct(
+            "LamportSignature",
+            &["digest_output_size", "values"],
+            LamportSignatureVisitor(PhantomData),
+        )
+    }
+}
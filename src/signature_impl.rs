@@ -0,0 +1,128 @@
+//! Glue between this crate's one-time keys and the `signature` crate's generic
+//! `Signer`/`Verifier` ecosystem, so Lamport keys can be used anywhere those traits are expected.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
+
+use digest::Digest;
+use signature::{DigestSigner, DigestVerifier, Error, Keypair, Signer, Verifier};
+
+use crate::{LamportSignatureData, PrivateKey, PublicKey};
+
+/// A Lamport signature, produced by [`Signer::try_sign`] and consumed by [`Verifier::verify`]
+pub struct LamportSignature<D: Digest + Clone>(LamportSignatureData, PhantomData<D>);
+
+impl<D: Digest + Clone> LamportSignature<D> {
+    pub(crate) fn from_data(data: LamportSignatureData) -> Self {
+        LamportSignature(data, PhantomData)
+    }
+
+    pub(crate) fn as_data(&self) -> &LamportSignatureData {
+        &self.0
+    }
+}
+
+impl<D: Digest + Clone> Clone for LamportSignature<D> {
+    fn clone(&self) -> Self {
+        LamportSignature(self.0.clone(), PhantomData)
+    }
+}
+
+impl<D: Digest + Clone> fmt::Debug for LamportSignature<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("LamportSignature").field(&self.0).finish()
+    }
+}
+
+impl<D: Digest + Clone> PartialEq for LamportSignature<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<D: Digest + Clone> Eq for LamportSignature<D> {}
+
+impl<D: Digest + Clone> signature::SignatureEncoding for LamportSignature<D> {
+    type Repr = Vec<u8>;
+}
+
+impl<D: Digest + Clone> TryFrom<&[u8]> for LamportSignature<D> {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+        let chunk_size = <D as Digest>::output_size();
+        let expected_chunks = chunk_size * 8;
+        if chunk_size == 0 || bytes.len() != chunk_size * expected_chunks {
+            return Err(Error::new());
+        }
+
+        let data = bytes.chunks(chunk_size).map(|chunk| chunk.to_vec()).collect();
+        Ok(LamportSignature(data, PhantomData))
+    }
+}
+
+impl<D: Digest + Clone> From<LamportSignature<D>> for Vec<u8> {
+    fn from(signature: LamportSignature<D>) -> Vec<u8> {
+        signature.0.into_iter().flatten().collect()
+    }
+}
+
+impl<D: Digest + Clone> Signer<LamportSignature<D>> for PrivateKey<D> {
+    fn try_sign(&self, msg: &[u8]) -> Result<LamportSignature<D>, Error> {
+        if self.used.get() {
+            return Err(Error::new());
+        }
+
+        let mut hasher = D::new();
+        hasher.update(msg);
+        let data_hash = hasher.finalize();
+
+        let data = self.sign_prehashed(&data_hash);
+        self.used.set(true);
+        Ok(LamportSignature(data, PhantomData))
+    }
+}
+
+impl<D: Digest + Clone> DigestSigner<D, LamportSignature<D>> for PrivateKey<D> {
+    fn try_sign_digest(&self, digest: D) -> Result<LamportSignature<D>, Error> {
+        if self.used.get() {
+            return Err(Error::new());
+        }
+
+        let data_hash = digest.finalize();
+        let data = self.sign_prehashed(&data_hash);
+        self.used.set(true);
+        Ok(LamportSignature(data, PhantomData))
+    }
+}
+
+impl<D: Digest + Clone> Keypair for PrivateKey<D> {
+    type VerifyingKey = PublicKey<D>;
+
+    fn verifying_key(&self) -> PublicKey<D> {
+        self.public_key()
+    }
+}
+
+impl<D: Digest + Clone> Verifier<LamportSignature<D>> for PublicKey<D> {
+    fn verify(&self, msg: &[u8], signature: &LamportSignature<D>) -> Result<(), Error> {
+        if self.verify_signature(&signature.0, msg) {
+            Ok(())
+        } else {
+            Err(Error::new())
+        }
+    }
+}
+
+impl<D: Digest + Clone> DigestVerifier<D, LamportSignature<D>> for PublicKey<D> {
+    fn verify_digest(&self, digest: D, signature: &LamportSignature<D>) -> Result<(), Error> {
+        let data_hash = digest.finalize();
+
+        if self.verify_prehashed(&signature.0, &data_hash) {
+            Ok(())
+        } else {
+            Err(Error::new())
+        }
+    }
+}
@@ -1,29 +1,34 @@
-use ring::digest::{Algorithm, SHA256, SHA512};
+use std::convert::TryFrom;
 
+use digest::Digest;
+use sha2::{Sha256, Sha512};
+use signature::{DigestSigner, DigestVerifier, Keypair, Signer, Verifier};
+use zeroize::Zeroize;
+
+use crate::LamportSignature;
+use crate::MerkleKeyPair;
 use crate::PrivateKey;
 use crate::PublicKey;
-
-static DIGEST_256: &Algorithm = &SHA256;
-static DIGEST_512: &Algorithm = &SHA512;
+use crate::WinternitzPrivateKey;
 
 #[cfg(test)]
 #[test]
 fn test_public_key_length_256() {
-    let pk = PrivateKey::new(DIGEST_256);
+    let pk = PrivateKey::<Sha256>::new();
     assert!(pk.public_key().one_values.len() == 256 && pk.public_key().zero_values.len() == 256);
 }
 
 #[test]
 fn test_public_key_length_512() {
-    let pk = PrivateKey::new(DIGEST_512);
+    let pk = PrivateKey::<Sha512>::new();
     assert!(pk.public_key().one_values.len() == 512 && pk.public_key().zero_values.len() == 512);
 }
 
 #[test]
 fn test_distinctive_successive_keygen() {
-    let mut past_buff = PrivateKey::new(DIGEST_512);
+    let mut past_buff = PrivateKey::<Sha512>::new();
     for _ in 0..100 {
-        let buffer = PrivateKey::new(DIGEST_512);
+        let buffer = PrivateKey::<Sha512>::new();
         assert!(past_buff != buffer);
         past_buff = buffer;
     }
@@ -31,7 +36,7 @@ fn test_distinctive_successive_keygen() {
 
 #[test]
 fn test_sign_verif() {
-    let mut priv_key = PrivateKey::new(DIGEST_512);
+    let mut priv_key = PrivateKey::<Sha512>::new();
     let data = b"Hello World";
     let signature = priv_key.sign(data).unwrap();
 
@@ -42,7 +47,7 @@ fn test_sign_verif() {
 
 #[test]
 fn test_sign_verif_sig_wrong_size() {
-    let mut priv_key = PrivateKey::new(DIGEST_512);
+    let mut priv_key = PrivateKey::<Sha512>::new();
     let data = b"Hello World";
     let mut too_short = priv_key.sign(data).unwrap();
     let extra = too_short.pop();
@@ -51,7 +56,7 @@ fn test_sign_verif_sig_wrong_size() {
 
     assert!(!pub_key.verify_signature(&too_short, data));
 
-    let mut priv_key = PrivateKey::new(DIGEST_512);
+    let mut priv_key = PrivateKey::<Sha512>::new();
     let data = b"Hello World";
     let mut too_long = priv_key.sign(data).unwrap();
     too_long.extend(extra);
@@ -61,7 +66,7 @@ fn test_sign_verif_sig_wrong_size() {
 
 #[test]
 fn test_sign_verif_fail() {
-    let mut priv_key = PrivateKey::new(DIGEST_512);
+    let mut priv_key = PrivateKey::<Sha512>::new();
     let data = b"Hello Word";
     let signature = priv_key.sign(data).unwrap();
 
@@ -72,9 +77,9 @@ fn test_sign_verif_fail() {
 
 #[test]
 fn test_serialization() {
-    let pub_key = PrivateKey::new(DIGEST_512).public_key();
+    let pub_key = PrivateKey::<Sha512>::new().public_key();
     let bytes = pub_key.to_bytes();
-    let recovered_pub_key = PublicKey::from_vec(bytes, DIGEST_512).unwrap();
+    let recovered_pub_key: PublicKey<Sha512> = PublicKey::from_vec(bytes).unwrap();
 
     assert_eq!(pub_key.one_values, recovered_pub_key.one_values);
     assert_eq!(pub_key.zero_values, recovered_pub_key.zero_values);
@@ -82,24 +87,24 @@ fn test_serialization() {
 
 #[test]
 fn test_serialization_wrong_size_key() {
-    let pub_key = PrivateKey::new(DIGEST_512).public_key();
+    let pub_key = PrivateKey::<Sha512>::new().public_key();
     let mut too_short = pub_key.to_bytes();
     let extra = too_short.pop();
-    assert!(PublicKey::from_vec(too_short, DIGEST_512).is_none());
+    assert!(PublicKey::<Sha512>::from_vec(too_short).is_none());
 
-    let pub_key = PrivateKey::new(DIGEST_512).public_key();
+    let pub_key = PrivateKey::<Sha512>::new().public_key();
     let mut too_long = pub_key.to_bytes();
     too_long.extend(extra);
-    assert!(PublicKey::from_vec(too_long, DIGEST_512).is_none());
+    assert!(PublicKey::<Sha512>::from_vec(too_long).is_none());
 }
 
 #[test]
 #[should_panic]
 fn test_serialization_panic() {
-    let pub_key = PrivateKey::new(DIGEST_512).public_key();
+    let pub_key = PrivateKey::<Sha512>::new().public_key();
     let mut bytes = pub_key.to_bytes();
     bytes.pop();
-    let recovered_pub_key = PublicKey::from_vec(bytes, DIGEST_512).unwrap();
+    let recovered_pub_key: PublicKey<Sha512> = PublicKey::from_vec(bytes).unwrap();
 
     assert_eq!(pub_key.one_values, recovered_pub_key.one_values);
     assert_eq!(pub_key.zero_values, recovered_pub_key.zero_values);
@@ -107,24 +112,252 @@ fn test_serialization_panic() {
 
 #[test]
 fn test_private_key_equality() {
-    let mut pub_key = PrivateKey::new(DIGEST_512);
-    let pub_key_2 = pub_key.clone();
+    let mut priv_key = PrivateKey::<Sha512>::new();
+    let priv_key_2 = priv_key.clone();
+
+    assert!(priv_key == priv_key_2);
+
+    priv_key.one_values.push(vec![0]);
+
+    assert!(priv_key != priv_key_2);
+
+    let mut priv_key = PrivateKey::<Sha512>::new();
+    let priv_key_2 = priv_key.clone();
+    priv_key.one_values.pop();
+
+    assert!(priv_key != priv_key_2);
+}
+
+#[test]
+fn test_public_key_equality() {
+    let priv_key = PrivateKey::<Sha512>::new();
+    let pub_key = priv_key.public_key();
+    let pub_key_2 = priv_key.public_key();
 
     assert!(pub_key == pub_key_2);
 
-    pub_key.one_values.push(vec![0]);
+    let other_priv_key = PrivateKey::<Sha512>::new();
+    assert!(pub_key != other_priv_key.public_key());
+}
+
+#[test]
+fn test_private_key_zeroize_clears_secrets() {
+    let mut priv_key = PrivateKey::<Sha512>::new();
+    priv_key.zeroize();
+
+    assert!(priv_key.zero_values.iter().all(|v| v.iter().all(|&b| b == 0)));
+    assert!(priv_key.one_values.iter().all(|v| v.iter().all(|&b| b == 0)));
+}
+
+#[test]
+fn test_merkle_sign_verif() {
+    let mut key_pair = MerkleKeyPair::<Sha256>::new(3);
+    let pub_key = key_pair.public_key();
+
+    let data = b"Hello World";
+    let signature = key_pair.sign(data).unwrap();
+
+    assert!(pub_key.verify_signature(&signature, data));
+}
+
+#[test]
+fn test_merkle_sign_verif_many_leaves() {
+    let mut key_pair = MerkleKeyPair::<Sha256>::new(3);
+    let pub_key = key_pair.public_key();
+
+    for i in 0..8 {
+        let data = format!("message {}", i);
+        let signature = key_pair.sign(data.as_bytes()).unwrap();
+        assert!(pub_key.verify_signature(&signature, data.as_bytes()));
+    }
+}
+
+#[test]
+fn test_merkle_sign_verif_fail() {
+    let mut key_pair = MerkleKeyPair::<Sha256>::new(3);
+    let pub_key = key_pair.public_key();
+
+    let data = b"Hello World";
+    let signature = key_pair.sign(data).unwrap();
+
+    assert!(!pub_key.verify_signature(&signature, b"Hello Word"));
+}
+
+#[test]
+fn test_merkle_verif_rejects_foreign_root() {
+    let mut key_pair = MerkleKeyPair::<Sha256>::new(3);
+    let other_key_pair = MerkleKeyPair::<Sha256>::new(3);
+    let other_pub_key = other_key_pair.public_key();
+
+    let data = b"Hello World";
+    let signature = key_pair.sign(data).unwrap();
+
+    assert!(!other_pub_key.verify_signature(&signature, data));
+}
+
+#[test]
+fn test_merkle_exhaustion() {
+    let mut key_pair = MerkleKeyPair::<Sha256>::new(0);
+
+    assert!(key_pair.sign(b"first").is_ok());
+    assert!(key_pair.sign(b"second").is_err());
+}
+
+#[test]
+fn test_winternitz_sign_verif() {
+    let mut priv_key = WinternitzPrivateKey::<Sha256>::new(4);
+    let data = b"Hello World";
+    let signature = priv_key.sign(data).unwrap();
+
+    let pub_key = priv_key.public_key();
+
+    assert!(pub_key.verify_signature(&signature, data));
+}
+
+#[test]
+fn test_winternitz_sign_verif_w8() {
+    let mut priv_key = WinternitzPrivateKey::<Sha256>::new(8);
+    let data = b"Hello World";
+    let signature = priv_key.sign(data).unwrap();
+
+    let pub_key = priv_key.public_key();
+
+    assert!(pub_key.verify_signature(&signature, data));
+}
+
+#[test]
+fn test_winternitz_sign_verif_fail() {
+    let mut priv_key = WinternitzPrivateKey::<Sha256>::new(4);
+    let data = b"Hello World";
+    let signature = priv_key.sign(data).unwrap();
+
+    let pub_key = priv_key.public_key();
+
+    assert!(!pub_key.verify_signature(&signature, b"Hello Word"));
+}
+
+#[test]
+fn test_winternitz_sign_twice_fails() {
+    let mut priv_key = WinternitzPrivateKey::<Sha256>::new(4);
+    assert!(priv_key.sign(b"first").is_ok());
+    assert!(priv_key.sign(b"second").is_err());
+}
+
+#[test]
+fn test_winternitz_tampered_signature_element_rejected() {
+    let mut priv_key = WinternitzPrivateKey::<Sha256>::new(4);
+    let data = b"Hello World";
+    let mut signature = priv_key.sign(data).unwrap();
+
+    let pub_key = priv_key.public_key();
+    signature[0].push(0);
+
+    assert!(!pub_key.verify_signature(&signature, data));
+}
+
+#[test]
+fn test_signer_verifier_round_trip() {
+    let priv_key = PrivateKey::<Sha512>::new();
+    let data = b"Hello World";
+    let signature: LamportSignature<Sha512> = priv_key.try_sign(data).unwrap();
+
+    let pub_key = priv_key.verifying_key();
+    assert!(pub_key.verify(data, &signature).is_ok());
+}
+
+#[test]
+fn test_signer_try_sign_rejects_reuse() {
+    let priv_key = PrivateKey::<Sha512>::new();
+    assert!(priv_key.try_sign(b"first").is_ok());
+    assert!(priv_key.try_sign(b"second").is_err());
+}
+
+#[test]
+fn test_digest_signer_digest_verifier_round_trip() {
+    let priv_key = PrivateKey::<Sha512>::new();
+    let data = b"Hello World";
+
+    let mut signing_hasher = Sha512::new();
+    signing_hasher.update(data);
+    let signature = priv_key.try_sign_digest(signing_hasher).unwrap();
+
+    let mut verifying_hasher = Sha512::new();
+    verifying_hasher.update(data);
+    let pub_key = priv_key.verifying_key();
+
+    assert!(pub_key.verify_digest(verifying_hasher, &signature).is_ok());
+}
+
+#[test]
+fn test_lamport_signature_try_from_bytes_round_trip() {
+    let priv_key = PrivateKey::<Sha512>::new();
+    let data = b"Hello World";
+    let signature: LamportSignature<Sha512> = priv_key.try_sign(data).unwrap();
+
+    let bytes: Vec<u8> = signature.into();
+    let recovered = LamportSignature::<Sha512>::try_from(bytes.as_slice()).unwrap();
+
+    let pub_key = priv_key.verifying_key();
+    assert!(pub_key.verify(data, &recovered).is_ok());
+}
+
+#[test]
+fn test_lamport_signature_try_from_rejects_truncated_bytes() {
+    let priv_key = PrivateKey::<Sha512>::new();
+    let signature: LamportSignature<Sha512> = priv_key.try_sign(b"Hello World").unwrap();
+
+    let mut bytes: Vec<u8> = signature.into();
+    bytes.truncate(128);
+
+    assert!(LamportSignature::<Sha512>::try_from(bytes.as_slice()).is_err());
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_public_key_serde_round_trip() {
+    let pub_key = PrivateKey::<Sha512>::new().public_key();
+    let json = serde_json::to_string(&pub_key).unwrap();
+    let recovered: PublicKey<Sha512> = serde_json::from_str(&json).unwrap();
 
-    assert!(pub_key != pub_key_2);
+    assert!(pub_key == recovered);
+}
 
-    let mut pub_key = PrivateKey::new(DIGEST_512);
-    let pub_key_2 = pub_key.clone();
-    pub_key.one_values.pop();
+#[test]
+#[cfg(feature = "serde")]
+fn test_private_key_serde_round_trip() {
+    let priv_key = PrivateKey::<Sha512>::new();
+    let json = serde_json::to_string(&priv_key).unwrap();
+    let recovered: PrivateKey<Sha512> = serde_json::from_str(&json).unwrap();
 
-    assert!(pub_key != pub_key_2);
+    assert!(priv_key == recovered);
+}
 
-    let mut pub_key = PrivateKey::new(DIGEST_512);
-    let pub_key_2 = pub_key.clone();
-    pub_key.algorithm = DIGEST_256;
+#[test]
+#[cfg(feature = "serde")]
+fn test_lamport_signature_serde_round_trip() {
+    let priv_key = PrivateKey::<Sha512>::new();
+    let data = b"Hello World";
+    let signature: LamportSignature<Sha512> = priv_key.try_sign(data).unwrap();
 
-    assert!(pub_key != pub_key_2);
+    let json = serde_json::to_string(&signature).unwrap();
+    let recovered: LamportSignature<Sha512> = serde_json::from_str(&json).unwrap();
+
+    let pub_key = priv_key.verifying_key();
+    assert!(pub_key.verify(data, &recovered).is_ok());
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_public_key_deserialize_rejects_wrong_digest_tag() {
+    let json = r#"{"digest_output_size":32,"zero_values":[],"one_values":[]}"#;
+    let result: Result<PublicKey<Sha512>, _> = serde_json::from_str(json);
+    assert!(result.is_err());
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_public_key_deserialize_rejects_malformed_values_shape() {
+    let json = r#"{"digest_output_size":32,"zero_values":[[1,2,3]],"one_values":[]}"#;
+    let result: Result<PublicKey<Sha256>, _> = serde_json::from_str(json);
+    assert!(result.is_err());
 }
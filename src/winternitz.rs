@@ -0,0 +1,287 @@
+//! Winternitz one-time signatures (W-OTS): a variant of the scheme that trades computation for
+//! signature size. Where a plain Lamport signature reveals one hash preimage per message *bit*,
+//! W-OTS groups the message digest into base-`2^w` digits and reveals one hash-chain value per
+//! digit, shrinking the signature (and public key) by roughly a factor of `w`.
+
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+
+use digest::Digest;
+use rand::OsRng;
+use rand::Rng;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
+
+/// A type alias defining a Winternitz signature
+pub type WinternitzSignatureData = Vec<Vec<u8>>;
+
+/// A one-time Winternitz signing private key, using a window of `w` bits per digit
+pub struct WinternitzPrivateKey<D: Digest + Clone> {
+    secrets: Vec<Vec<u8>>,
+    w: u32,
+    used: Cell<bool>,
+    digest: PhantomData<D>,
+}
+
+impl<D: Digest + Clone> Clone for WinternitzPrivateKey<D> {
+    fn clone(&self) -> Self {
+        WinternitzPrivateKey {
+            secrets: self.secrets.clone(),
+            w: self.w,
+            used: self.used.clone(),
+            digest: PhantomData,
+        }
+    }
+}
+
+impl<D: Digest + Clone> fmt::Debug for WinternitzPrivateKey<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WinternitzPrivateKey")
+            .field("w", &self.w)
+            .field("used", &self.used.get())
+            .finish()
+    }
+}
+
+impl<D: Digest + Clone> PartialEq for WinternitzPrivateKey<D> {
+    // Constant-time so that comparing two private keys doesn't leak which secret differs first
+    fn eq(&self, other: &Self) -> bool {
+        if self.w != other.w || self.secrets.len() != other.secrets.len() {
+            return false;
+        }
+
+        let mut result = Choice::from(1u8);
+        for i in 0..self.secrets.len() {
+            result &= self.secrets[i].as_slice().ct_eq(other.secrets[i].as_slice());
+        }
+        result.into()
+    }
+}
+
+impl<D: Digest + Clone> Zeroize for WinternitzPrivateKey<D> {
+    fn zeroize(&mut self) {
+        self.secrets.zeroize();
+    }
+}
+
+// Hand-written, not derived: the digest `D` never actually holds secret data here (it only
+// shows up as a `PhantomData` tag), so deriving would needlessly force every digest type used
+// with this key to also implement `Zeroize`
+impl<D: Digest + Clone> zeroize::ZeroizeOnDrop for WinternitzPrivateKey<D> {}
+
+impl<D: Digest + Clone> Drop for WinternitzPrivateKey<D> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<D: Digest + Clone> WinternitzPrivateKey<D> {
+    /// Generates a new random Winternitz key using a window of `w` bits per digit (commonly 4 or
+    /// 8). `w` must evenly divide 8. This method can panic if OS RNG fails
+    pub fn new(w: u32) -> WinternitzPrivateKey<D> {
+        assert!(w > 0 && 8 % w == 0, "w must evenly divide 8 (e.g. 4 or 8)");
+
+        let (msg_digit_count, checksum_digit_count) = digit_counts::<D>(w);
+        let total_secrets = msg_digit_count + checksum_digit_count;
+        let output_size = <D as Digest>::output_size();
+
+        let mut rng = match OsRng::new() {
+            Ok(g) => g,
+            Err(e) => panic!("Failed to obtain OS RNG: {}", e),
+        };
+
+        let secrets = (0..total_secrets)
+            .map(|_| {
+                let mut secret = vec![0u8; output_size];
+                rng.fill_bytes(&mut secret);
+                secret
+            })
+            .collect();
+
+        WinternitzPrivateKey {
+            secrets,
+            w,
+            used: Cell::new(false),
+            digest: PhantomData,
+        }
+    }
+
+    /// Returns the public key associated with this private key
+    pub fn public_key(&self) -> WinternitzPublicKey<D> {
+        let chain_length = (1u32 << self.w) - 1;
+
+        let chain_ends = self.secrets
+            .iter()
+            .map(|secret| hash_chain::<D>(secret, chain_length))
+            .collect();
+
+        WinternitzPublicKey {
+            chain_ends,
+            w: self.w,
+            digest: PhantomData,
+        }
+    }
+
+    /// Signs the data with the private key and returns the result if successful.
+    /// If unsuccesful, an explanation string is returned
+    pub fn sign(&mut self, data: &[u8]) -> Result<WinternitzSignatureData, &'static str> {
+        if self.used.get() {
+            return Err("Attempting to sign more than once.");
+        }
+
+        let mut hasher = D::new();
+        hasher.update(data);
+        let data_hash = hasher.finalize();
+
+        let (msg_digit_count, checksum_digit_count) = digit_counts::<D>(self.w);
+        let msg_digits = extract_digits(&data_hash, self.w, msg_digit_count);
+        let digits: Vec<u32> = msg_digits
+            .iter()
+            .cloned()
+            .chain(checksum_digits(&msg_digits, self.w, checksum_digit_count))
+            .collect();
+
+        let signature = self.secrets
+            .iter()
+            .zip(digits.iter())
+            .map(|(secret, digit)| hash_chain::<D>(secret, *digit))
+            .collect();
+
+        self.used.set(true);
+        Ok(signature)
+    }
+}
+
+/// A one-time Winternitz signing public key, using a window of `w` bits per digit
+#[derive(Clone)]
+pub struct WinternitzPublicKey<D: Digest + Clone> {
+    chain_ends: Vec<Vec<u8>>,
+    w: u32,
+    digest: PhantomData<D>,
+}
+
+impl<D: Digest + Clone> fmt::Debug for WinternitzPublicKey<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WinternitzPublicKey")
+            .field("chain_ends", &self.chain_ends)
+            .field("w", &self.w)
+            .finish()
+    }
+}
+
+impl<D: Digest + Clone> PartialEq for WinternitzPublicKey<D> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.w != other.w || self.chain_ends.len() != other.chain_ends.len() {
+            return false;
+        }
+
+        let mut result = Choice::from(1u8);
+        for i in 0..self.chain_ends.len() {
+            result &= self.chain_ends[i].as_slice().ct_eq(other.chain_ends[i].as_slice());
+        }
+        result.into()
+    }
+}
+
+impl<D: Digest + Clone> Eq for WinternitzPublicKey<D> {}
+
+impl<D: Digest + Clone> WinternitzPublicKey<D> {
+    /// Verifies that the signature of the data is correctly signed with the given key.
+    /// Continues each hash chain the remaining steps and compares the results, including the
+    /// checksum digits, against the public chain ends. Runs in constant time with respect to
+    /// which chain, if any, fails to match
+    pub fn verify_signature(&self, signature: &WinternitzSignatureData, data: &[u8]) -> bool {
+        let (msg_digit_count, checksum_digit_count) = digit_counts::<D>(self.w);
+        let total_digits = msg_digit_count + checksum_digit_count;
+
+        if signature.len() != total_digits || self.chain_ends.len() != total_digits {
+            return false;
+        }
+
+        let mut hasher = D::new();
+        hasher.update(data);
+        let data_hash = hasher.finalize();
+
+        let msg_digits = extract_digits(&data_hash, self.w, msg_digit_count);
+        let digits: Vec<u32> = msg_digits
+            .iter()
+            .cloned()
+            .chain(checksum_digits(&msg_digits, self.w, checksum_digit_count))
+            .collect();
+
+        let chain_length = (1u32 << self.w) - 1;
+
+        let mut result = Choice::from(1u8);
+        for ((element, digit), expected) in signature.iter().zip(digits.iter()).zip(self.chain_ends.iter()) {
+            let remaining_steps = chain_length - digit;
+            let completed = hash_chain::<D>(element, remaining_steps);
+            result &= completed.as_slice().ct_eq(expected.as_slice());
+        }
+
+        result.into()
+    }
+}
+
+/// Returns `(message_digit_count, checksum_digit_count)` for a digest `D` under window `w`
+fn digit_counts<D: Digest>(w: u32) -> (usize, usize) {
+    let output_bits = <D as Digest>::output_size() * 8;
+    let msg_digit_count = output_bits.div_ceil(w as usize);
+
+    let max_digit = (1u32 << w) - 1;
+    let max_checksum = msg_digit_count as u32 * max_digit;
+
+    let mut checksum_digit_count = 1;
+    let mut remaining = max_checksum >> w;
+    while remaining > 0 {
+        checksum_digit_count += 1;
+        remaining >>= w;
+    }
+
+    (msg_digit_count, checksum_digit_count)
+}
+
+/// Splits `data_hash` into `count` big-endian base-`2^w` digits
+fn extract_digits(data_hash: &[u8], w: u32, count: usize) -> Vec<u32> {
+    let digits_per_byte = 8 / w;
+    let mask = (1u32 << w) - 1;
+
+    let mut digits = Vec::with_capacity(count);
+    for byte in data_hash {
+        for k in 0..digits_per_byte {
+            if digits.len() == count {
+                return digits;
+            }
+            let shift = (digits_per_byte - 1 - k) * w;
+            digits.push(((*byte as u32) >> shift) & mask);
+        }
+    }
+    digits
+}
+
+/// Encodes the Winternitz checksum of `msg_digits` as `count` big-endian base-`2^w` digits.
+/// Summing `2^w - 1 - b_i` this way means an attacker who increments a message digit in a forged
+/// signature must also decrement a checksum digit, which they cannot do without its preimage
+fn checksum_digits(msg_digits: &[u32], w: u32, count: usize) -> Vec<u32> {
+    let mask = (1u32 << w) - 1;
+    let checksum: u32 = msg_digits.iter().map(|digit| mask - digit).sum();
+
+    let mut digits = vec![0u32; count];
+    let mut remaining = checksum;
+    for digit in digits.iter_mut().rev() {
+        *digit = remaining & mask;
+        remaining >>= w;
+    }
+    digits
+}
+
+/// Hashes `start` with `D` repeatedly, `steps` times
+fn hash_chain<D: Digest>(start: &[u8], steps: u32) -> Vec<u8> {
+    let mut value = start.to_vec();
+    for _ in 0..steps {
+        let mut hasher = D::new();
+        hasher.update(&value);
+        value = hasher.finalize().to_vec();
+    }
+    value
+}